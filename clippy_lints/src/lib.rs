@@ -0,0 +1,20 @@
+// Copyright 2014-2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+mod needless_pass_by_value;
+mod utils;
+
+use crate::utils::conf::Conf;
+
+/// Registers this crate's lint passes, reading configurable thresholds out of `conf`.
+pub fn register_plugins(reg: &mut rustc_plugin::Registry<'_>, conf: &Conf) {
+    reg.register_late_lint_pass(box needless_pass_by_value::NeedlessPassByValue::new(Some(
+        conf.pass_by_value_size_limit,
+    )));
+}
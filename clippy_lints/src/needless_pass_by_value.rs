@@ -7,7 +7,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::rustc::hir::intravisit::FnKind;
+use crate::rustc::hir::def::Def;
+use crate::rustc::hir::intravisit::{self, FnKind};
 use crate::rustc::hir::*;
 use crate::rustc::lint::{LateContext, LateLintPass, LintArray, LintPass};
 use crate::rustc::middle::expr_use_visitor as euv;
@@ -23,8 +24,8 @@ use crate::syntax::errors::DiagnosticBuilder;
 use crate::syntax_pos::Span;
 use crate::utils::ptr::get_spans;
 use crate::utils::{
-    get_trait_def_id, implements_trait, in_macro, is_copy, is_self, match_type, multispan_sugg, paths, snippet,
-    snippet_opt, span_lint_and_then,
+    get_trait_def_id, implements_trait, in_macro, is_copy, is_self, match_type, multispan_sugg_with_applicability,
+    paths, snippet, snippet_opt, span_lint_and_then,
 };
 use if_chain::if_chain;
 use matches::matches;
@@ -59,11 +60,49 @@ declare_clippy_lint! {
     "functions taking arguments by value, but not consuming them in its body"
 }
 
-pub struct NeedlessPassByValue;
+/// **What it does:** Checks for functions taking a `&mut T` argument, but not
+/// mutating it in its body.
+///
+/// **Why is this bad?** Requiring a mutable reference unnecessarily restricts
+/// callers and hides the fact that the function only reads the value.
+///
+/// **Known problems:**
+/// * Same caveat as `NEEDLESS_PASS_BY_VALUE`: sometimes the mutable reference
+/// is part of a deliberate API design, even if it's not mutated today.
+///
+/// **Example:**
+/// ```rust
+/// fn foo(v: &mut Vec<i32>) {
+///     assert_eq!(v.len(), 42);
+/// }
+/// // should be
+/// fn foo(v: &Vec<i32>) {
+///     assert_eq!(v.len(), 42);
+/// }
+/// ```
+declare_clippy_lint! {
+    pub NEEDLESS_PASS_BY_REF_MUT,
+    pedantic,
+    "functions taking a `&mut T` argument, but not mutating it in its body"
+}
+
+pub struct NeedlessPassByValue {
+    pass_by_value_size_limit: u64,
+}
+
+impl NeedlessPassByValue {
+    /// `None` defaults to twice the pointer size of the host Clippy was built for (not
+    /// that of the target being checked).
+    pub fn new(pass_by_value_size_limit: Option<u64>) -> Self {
+        let pass_by_value_size_limit =
+            pass_by_value_size_limit.unwrap_or_else(|| 2 * std::mem::size_of::<usize>() as u64);
+        Self { pass_by_value_size_limit }
+    }
+}
 
 impl LintPass for NeedlessPassByValue {
     fn get_lints(&self) -> LintArray {
-        lint_array![NEEDLESS_PASS_BY_VALUE]
+        lint_array![NEEDLESS_PASS_BY_VALUE, NEEDLESS_PASS_BY_REF_MUT]
     }
 }
 
@@ -77,6 +116,123 @@ macro_rules! need {
     };
 }
 
+/// An owned type with a fixed-name borrowed counterpart (unlike `Vec`/`Box`, which
+/// need their generic argument spliced in).
+struct OwnedToBorrowed {
+    /// `paths::` constant for the owned type.
+    owned_path: &'static [&'static str],
+    /// Borrowed type to suggest, without the leading `&`.
+    borrowed_ty: &'static str,
+    /// `get_spans` rename pairs for owned-only call sites (e.g. `.clone()`).
+    renames: &'static [(&'static str, &'static str)],
+    /// `&self` inherent methods the borrowed type lacks (`&mut self`/consuming ones are
+    /// already ruled out by the `moved_vars`/binding-mode checks in `check_fn`).
+    owned_only_methods: &'static [&'static str],
+}
+
+static OWNED_TO_BORROWED: &[OwnedToBorrowed] = &[
+    OwnedToBorrowed {
+        owned_path: &paths::STRING,
+        borrowed_ty: "str",
+        renames: &[("clone", ".to_string()"), ("as_str", "")],
+        owned_only_methods: &["capacity"],
+    },
+    OwnedToBorrowed {
+        owned_path: &paths::PATH_BUF,
+        borrowed_ty: "Path",
+        renames: &[("clone", ".to_path_buf()")],
+        owned_only_methods: &["capacity"],
+    },
+    OwnedToBorrowed {
+        owned_path: &paths::OS_STRING,
+        borrowed_ty: "OsStr",
+        renames: &[("clone", ".to_os_string()")],
+        owned_only_methods: &["capacity"],
+    },
+    OwnedToBorrowed {
+        owned_path: &paths::CSTRING,
+        borrowed_ty: "CStr",
+        renames: &[("clone", ".to_owned()")],
+        owned_only_methods: &[],
+    },
+];
+
+/// Vec's `&self` inherent methods with no `&[T]` equivalent.
+const VEC_OWNED_ONLY_METHODS: &[&str] = &["capacity"];
+/// Box has no non-consuming inherent methods besides `Deref`'s, so nothing to list.
+const BOX_OWNED_ONLY_METHODS: &[&str] = &[];
+
+/// `true` if `body` calls one of `owned_only_methods` on `canonical_id`, other than
+/// through a name already covered by `renames`. Such a call relies on an inherent
+/// method the borrowed type doesn't have, so the rewrite isn't safe to auto-apply.
+fn uses_owned_only_method(body: &Body, canonical_id: NodeId, renames: &[(&str, &str)], owned_only_methods: &[&str]) -> bool {
+    struct V<'a> {
+        canonical_id: NodeId,
+        renames: &'a [(&'a str, &'a str)],
+        owned_only_methods: &'a [&'a str],
+        found: bool,
+    }
+
+    impl<'a, 'tcx> intravisit::Visitor<'tcx> for V<'a> {
+        fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+            intravisit::NestedVisitorMap::None
+        }
+
+        fn visit_expr(&mut self, expr: &'tcx Expr) {
+            if_chain! {
+                if let ExprKind::MethodCall(ref seg, _, ref args) = expr.node;
+                if let ExprKind::Path(QPath::Resolved(_, ref path)) = args[0].node;
+                if let Def::Local(vid) = path.def;
+                if vid == self.canonical_id;
+                then {
+                    let name = seg.ident.as_str();
+                    let renamed = self.renames.iter().any(|&(from, _)| from == &*name);
+                    if !renamed && self.owned_only_methods.contains(&&*name) {
+                        self.found = true;
+                    }
+                }
+            }
+            intravisit::walk_expr(self, expr);
+        }
+    }
+
+    let mut v = V {
+        canonical_id,
+        renames,
+        owned_only_methods,
+        found: false,
+    };
+    intravisit::walk_body(&mut v, body);
+    v.found
+}
+
+/// Emits the "change the type to" suggestion for `input_span`, plus one "change the
+/// call to" suggestion per rewrite in `clone_spans`, all at `applicability`.
+fn emit_ty_and_call_site_suggestions(
+    db: &mut DiagnosticBuilder<'_>,
+    input_span: Span,
+    borrowed_ty: &str,
+    clone_spans: Vec<(Span, &'static str)>,
+    cx: &LateContext<'_, '_>,
+    applicability: Applicability,
+) {
+    db.span_suggestion_with_applicability(
+        input_span,
+        "consider changing the type to",
+        borrowed_ty.to_string(),
+        applicability,
+    );
+
+    for (span, suggestion) in clone_spans {
+        db.span_suggestion_with_applicability(
+            span,
+            &snippet_opt(cx, span).map_or("change the call to".into(), |x| Cow::from(format!("change `{}` to", x))),
+            suggestion.into(),
+            applicability,
+        );
+    }
+}
+
 impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
     fn check_fn(
         &mut self,
@@ -148,6 +304,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
         let MovedVariablesCtxt {
             moved_vars,
             spans_need_deref,
+            mutated_vars,
             ..
         } = {
             let mut ctx = MovedVariablesCtxt::new(cx);
@@ -175,6 +332,40 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
                 }
             }
 
+            if_chain! {
+                if !is_self(arg);
+                if ty.is_mutable_pointer();
+                if let PatKind::Binding(_, canonical_id, ..) = arg.pat.node;
+                // If the parameter itself is moved into another binding, mutation could
+                // happen through that binding instead; `mutated_vars` only tracks writes
+                // through the original id, so bail out rather than risk a false positive.
+                if !moved_vars.contains(&canonical_id);
+                if !mutated_vars.contains(&canonical_id);
+                if let TyKind::Rptr(lifetime, MutTy { ty: ref inner_ty, .. }) = input.node;
+                then {
+                    let lifetime_snip = if lifetime.is_elided() {
+                        String::new()
+                    } else {
+                        format!("{} ", snippet(cx, lifetime.span, "'_"))
+                    };
+                    span_lint_and_then(
+                        cx,
+                        NEEDLESS_PASS_BY_REF_MUT,
+                        input.span,
+                        "this argument is a mutable reference, but not mutated in the function body",
+                        |db| {
+                            db.span_suggestion_with_applicability(
+                                input.span,
+                                "consider changing to",
+                                format!("&{}{}", lifetime_snip, snippet(cx, inner_ty.span, "_")),
+                                Applicability::Unspecified,
+                            );
+                        },
+                    );
+                    continue;
+                }
+            }
+
             //
             // * Exclude a type that is specifically bounded by `Borrow`.
             // * Exclude a type whose reference also fulfills its bound. (e.g. `std::convert::AsRef`,
@@ -209,6 +400,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
                 if !whitelisted_traits.iter().any(|&t| implements_trait(cx, ty, t, &[]));
                 if !implements_borrow_trait;
                 if !all_borrowable_trait;
+                if exceeds_size_limit(cx, ty, self.pass_by_value_size_limit);
 
                 if let PatKind::Binding(mode, canonical_id, ..) = arg.pat.node;
                 if !moved_vars.contains(&canonical_id);
@@ -228,6 +420,10 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
                         }
 
                         let deref_span = spans_need_deref.get(&canonical_id);
+
+                        // `Vec<T>` and `Box<T>` need their inner generic argument spliced into the
+                        // suggested borrowed type, so they can't be driven by `OWNED_TO_BORROWED`
+                        // below and are handled up front instead.
                         if_chain! {
                             if match_type(cx, ty, &paths::VEC);
                             if let Some(clone_spans) =
@@ -242,62 +438,74 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
                                 }).unwrap());
                             then {
                                 let slice_ty = format!("&[{}]", snippet(cx, elem_ty.span, "_"));
-                                db.span_suggestion_with_applicability(
-                                    input.span,
-                                    "consider changing the type to",
-                                    slice_ty,
-                                    Applicability::Unspecified,
-                                );
-
-                                for (span, suggestion) in clone_spans {
-                                    db.span_suggestion_with_applicability(
-                                        span,
-                                        &snippet_opt(cx, span)
-                                            .map_or(
-                                                "change the call to".into(),
-                                                |x| Cow::from(format!("change `{}` to", x)),
-                                            ),
-                                        suggestion.into(),
-                                        Applicability::Unspecified,
-                                    );
-                                }
-
                                 // cannot be destructured, no need for `*` suggestion
                                 assert!(deref_span.is_none());
+                                let applicability = if uses_owned_only_method(body, canonical_id, &[("clone", ".to_owned()")], VEC_OWNED_ONLY_METHODS) {
+                                    Applicability::Unspecified
+                                } else {
+                                    Applicability::MachineApplicable
+                                };
+                                emit_ty_and_call_site_suggestions(db, input.span, &slice_ty, clone_spans, cx, applicability);
                                 return;
                             }
                         }
 
-                        if match_type(cx, ty, &paths::STRING) {
+                        if_chain! {
+                            if ty.is_box();
                             if let Some(clone_spans) =
-                                get_spans(cx, Some(body.id()), idx, &[("clone", ".to_string()"), ("as_str", "")]) {
-                                db.span_suggestion_with_applicability(
-                                    input.span,
-                                    "consider changing the type to",
-                                    "&str".to_string(),
-                                    Applicability::Unspecified,
-                                );
-
-                                for (span, suggestion) in clone_spans {
-                                    db.span_suggestion_with_applicability(
-                                        span,
-                                        &snippet_opt(cx, span)
-                                            .map_or(
-                                                "change the call to".into(),
-                                                |x| Cow::from(format!("change `{}` to", x))
-                                            ),
-                                        suggestion.into(),
-                                        Applicability::Unspecified,
-                                    );
-                                }
-
+                                get_spans(cx, Some(body.id()), idx, &[("clone", "")]);
+                            if let TyKind::Path(QPath::Resolved(_, ref path)) = input.node;
+                            if let Some(inner_ty) = path.segments.iter()
+                                .find(|seg| seg.ident.name == "Box")
+                                .and_then(|ps| ps.args.as_ref())
+                                .map(|params| params.args.iter().find_map(|arg| match arg {
+                                    GenericArg::Type(ty) => Some(ty),
+                                    GenericArg::Lifetime(_) => None,
+                                }).unwrap());
+                            then {
+                                let ref_ty = format!("&{}", snippet(cx, inner_ty.span, "_"));
                                 assert!(deref_span.is_none());
+                                let applicability = if uses_owned_only_method(body, canonical_id, &[("clone", "")], BOX_OWNED_ONLY_METHODS) {
+                                    Applicability::Unspecified
+                                } else {
+                                    Applicability::MachineApplicable
+                                };
+                                emit_ty_and_call_site_suggestions(db, input.span, &ref_ty, clone_spans, cx, applicability);
                                 return;
                             }
                         }
 
+                        // Owned types whose borrowed counterpart is a fixed, non-generic name.
+                        for suggestion in OWNED_TO_BORROWED {
+                            if_chain! {
+                                if match_type(cx, ty, suggestion.owned_path);
+                                if let Some(clone_spans) =
+                                    get_spans(cx, Some(body.id()), idx, suggestion.renames);
+                                then {
+                                    let borrowed_ty = format!("&{}", suggestion.borrowed_ty);
+                                    assert!(deref_span.is_none());
+                                    let applicability = if uses_owned_only_method(body, canonical_id, suggestion.renames, suggestion.owned_only_methods) {
+                                        Applicability::Unspecified
+                                    } else {
+                                        Applicability::MachineApplicable
+                                    };
+                                    emit_ty_and_call_site_suggestions(db, input.span, &borrowed_ty, clone_spans, cx, applicability);
+                                    return;
+                                }
+                            }
+                        }
+
                         let mut spans = vec![(input.span, format!("&{}", snippet(cx, input.span, "_")))];
 
+                        // A plain `&` suggestion is machine-applicable as long as there's no
+                        // destructuring use needing a `*` patched in too; once one is, the two
+                        // edits interact and we fall back to asking the user to apply it by hand.
+                        let applicability = if deref_span.is_none() {
+                            Applicability::MachineApplicable
+                        } else {
+                            Applicability::Unspecified
+                        };
+
                         // Suggests adding `*` to dereference the added reference.
                         if let Some(deref_span) = deref_span {
                             spans.extend(
@@ -308,7 +516,12 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NeedlessPassByValue {
                             );
                             spans.sort_by_key(|&(span, _)| span);
                         }
-                        multispan_sugg(db, "consider taking a reference instead".to_string(), spans);
+                        multispan_sugg_with_applicability(
+                            db,
+                            "consider taking a reference instead".to_string(),
+                            applicability,
+                            spans,
+                        );
                     };
 
                     span_lint_and_then(
@@ -330,6 +543,10 @@ struct MovedVariablesCtxt<'a, 'tcx: 'a> {
     /// Spans which need to be prefixed with `*` for dereferencing the
     /// suggested additional reference.
     spans_need_deref: FxHashMap<NodeId, FxHashSet<Span>>,
+    /// Locals that are written through, directly or through a `&mut` re-borrow. Used by
+    /// `NEEDLESS_PASS_BY_REF_MUT` to tell a `&mut` parameter that's genuinely mutated
+    /// apart from one that's only ever read.
+    mutated_vars: FxHashSet<NodeId>,
 }
 
 impl<'a, 'tcx> MovedVariablesCtxt<'a, 'tcx> {
@@ -338,6 +555,7 @@ impl<'a, 'tcx> MovedVariablesCtxt<'a, 'tcx> {
             cx,
             moved_vars: FxHashSet::default(),
             spans_need_deref: FxHashMap::default(),
+            mutated_vars: FxHashSet::default(),
         }
     }
 
@@ -424,18 +642,41 @@ impl<'a, 'tcx> euv::Delegate<'tcx> for MovedVariablesCtxt<'a, 'tcx> {
         &mut self,
         _: NodeId,
         _: Span,
-        _: &mc::cmt_<'tcx>,
+        cmt: &mc::cmt_<'tcx>,
         _: ty::Region<'_>,
-        _: ty::BorrowKind,
+        bk: ty::BorrowKind,
         _: euv::LoanCause,
     ) {
+        // A re-borrow as `&mut` can be used to write through, so treat it the same as an
+        // actual write for the purposes of `NEEDLESS_PASS_BY_REF_MUT`.
+        if let ty::BorrowKind::MutBorrow = bk {
+            if let Some(vid) = innermost_local(cmt) {
+                self.mutated_vars.insert(vid);
+            }
+        }
     }
 
-    fn mutate(&mut self, _: NodeId, _: Span, _: &mc::cmt_<'tcx>, _: euv::MutateMode) {}
+    fn mutate(&mut self, _: NodeId, _: Span, cmt: &mc::cmt_<'tcx>, _: euv::MutateMode) {
+        if let Some(vid) = innermost_local(cmt) {
+            self.mutated_vars.insert(vid);
+        }
+    }
 
     fn decl_without_init(&mut self, _: NodeId, _: Span) {}
 }
 
+/// Peels `Downcast`/`Interior`/`Deref` categorizations to find the local variable (if
+/// any) that a place ultimately writes through, so that mutating `*arg` is attributed
+/// back to the `arg` parameter itself.
+fn innermost_local<'tcx>(cmt: &mc::cmt_<'tcx>) -> Option<NodeId> {
+    let cmt = unwrap_downcast_or_interior(cmt);
+    match cmt.cat {
+        mc::Categorization::Local(vid) => Some(vid),
+        mc::Categorization::Deref(ref base, _) => innermost_local(base),
+        _ => None,
+    }
+}
+
 fn unwrap_downcast_or_interior<'a, 'tcx>(mut cmt: &'a mc::cmt_<'tcx>) -> mc::cmt_<'tcx> {
     loop {
         match cmt.cat {
@@ -446,3 +687,11 @@ fn unwrap_downcast_or_interior<'a, 'tcx>(mut cmt: &'a mc::cmt_<'tcx>) -> mc::cmt
         }
     }
 }
+
+/// Is `ty`'s layout over `limit` bytes? Unlayoutable types count as exceeding it.
+fn exceeds_size_limit<'a, 'tcx>(cx: &LateContext<'a, 'tcx>, ty: ty::Ty<'tcx>, limit: u64) -> bool {
+    match cx.tcx.layout_of(cx.param_env.and(ty)) {
+        Ok(layout) => layout.size.bytes() > limit,
+        Err(_) => true,
+    }
+}
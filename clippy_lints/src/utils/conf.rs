@@ -0,0 +1,56 @@
+// Copyright 2014-2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parses `clippy.toml` into the `Conf` values lints read their configurable
+//! thresholds from.
+
+use serde_derive::Deserialize;
+use std::{fs, io, path::Path};
+
+/// Default for `pass-by-value-size-limit`: twice the pointer size of the host Clippy
+/// was built for (not that of the target being checked).
+fn default_pass_by_value_size_limit() -> u64 {
+    2 * std::mem::size_of::<usize>() as u64
+}
+
+/// Values read from `clippy.toml`, with defaults for anything left unset.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct Conf {
+    /// Lint: NEEDLESS_PASS_BY_VALUE. Types at or under this size (in bytes) aren't
+    /// flagged for being passed by value instead of by reference.
+    #[serde(rename = "pass-by-value-size-limit")]
+    pub pass_by_value_size_limit: u64,
+}
+
+impl Default for Conf {
+    fn default() -> Self {
+        Self {
+            pass_by_value_size_limit: default_pass_by_value_size_limit(),
+        }
+    }
+}
+
+/// Reads `clippy.toml` from `path`, falling back to `Conf::default()` if it doesn't
+/// exist; reports unknown keys and parse errors to `stderr` rather than failing.
+pub fn read(path: &Path) -> Conf {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Conf::default(),
+        Err(e) => {
+            eprintln!("error reading Clippy's configuration file `{}`: {}", path.display(), e);
+            return Conf::default();
+        },
+    };
+
+    toml::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("error parsing Clippy's configuration file `{}`: {}", path.display(), e);
+        Conf::default()
+    })
+}
@@ -0,0 +1,39 @@
+// Copyright 2014-2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `../needless_pass_by_value_size_limit/clippy.toml` sets
+//! `pass-by-value-size-limit = 16`.
+
+#![warn(clippy::needless_pass_by_value)]
+
+// Exactly at the limit: should NOT lint.
+struct AtLimit {
+    a: u64,
+    b: u64,
+}
+
+fn at_limit(v: AtLimit) -> u64 {
+    v.a
+}
+
+// One u64 over the limit: should lint.
+struct OverLimit {
+    a: u64,
+    b: u64,
+    c: u64,
+}
+
+fn over_limit(v: OverLimit) -> u64 {
+    v.a
+}
+
+fn main() {
+    at_limit(AtLimit { a: 1, b: 2 });
+    over_limit(OverLimit { a: 1, b: 2, c: 3 });
+}
@@ -0,0 +1,36 @@
+// Copyright 2014-2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![warn(clippy::needless_pass_by_ref_mut)]
+
+// Never mutated: should lint.
+fn never_mutated(v: &mut Vec<i32>) -> usize {
+    v.len()
+}
+
+// Mutated directly: should NOT lint.
+fn mutated(v: &mut Vec<i32>) {
+    v.push(1);
+}
+
+// Mutated through a `&mut` re-borrow: should NOT lint.
+fn reborrowed_mut(v: &mut Vec<i32>) {
+    push_one(v);
+}
+
+fn push_one(v: &mut Vec<i32>) {
+    v.push(1);
+}
+
+fn main() {
+    let mut v = vec![1, 2, 3];
+    never_mutated(&mut v);
+    mutated(&mut v);
+    reborrowed_mut(&mut v);
+}